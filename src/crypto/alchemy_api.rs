@@ -0,0 +1,186 @@
+use ethers::types::U256;
+use ethers::utils::format_ether;
+use serde::Deserialize;
+use serde_json::json;
+use std::env;
+
+use super::FeeEstimate;
+
+const FEE_HISTORY_BLOCK_COUNT: u64 = 20;
+const REWARD_PERCENTILES: [f64; 3] = [10.0, 50.0, 90.0];
+
+fn alchemy_url() -> String {
+    format!("https://eth-mainnet.g.alchemy.com/v2/{}", env::var("ALCHEMY_API_KEY").expect("ALCHEMY_API_KEY not set"))
+}
+
+fn wallet_address() -> String {
+    env::var("WALLET_ADDRESS").expect("WALLET_ADDRESS not set")
+}
+
+#[derive(Deserialize)]
+struct JsonRpcResponse<T> {
+    result: T,
+}
+
+#[derive(Deserialize)]
+struct TokenBalance {
+    #[serde(rename = "contractAddress")]
+    contract_address: String,
+    #[serde(rename = "tokenBalance")]
+    token_balance: String,
+}
+
+#[derive(Deserialize)]
+struct TokenBalancesResult {
+    #[serde(rename = "tokenBalances")]
+    token_balances: Vec<TokenBalance>,
+}
+
+pub async fn get_eth_balance() -> String {
+    let body = json!({
+        "jsonrpc": "2.0",
+        "method": "eth_getBalance",
+        "params": [wallet_address(), "latest"],
+        "id": 1
+    });
+
+    let res: JsonRpcResponse<String> = reqwest::Client::new()
+        .post(alchemy_url())
+        .json(&body)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    let wei = U256::from_str_radix(res.result.trim_start_matches("0x"), 16).unwrap();
+    format!("{} ETH", format_ether(wei))
+}
+
+pub async fn get_token_balances() -> Vec<String> {
+    let body = json!({
+        "jsonrpc": "2.0",
+        "method": "alchemy_getTokenBalances",
+        "params": [wallet_address(), "erc20"],
+        "id": 1
+    });
+
+    let res: JsonRpcResponse<TokenBalancesResult> = reqwest::Client::new()
+        .post(alchemy_url())
+        .json(&body)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    res.result.token_balances
+        .iter()
+        .map(|tb| format!("{}: {}", tb.contract_address, tb.token_balance))
+        .collect()
+}
+
+pub async fn get_gas() -> f64 {
+    let body = json!({
+        "jsonrpc": "2.0",
+        "method": "eth_gasPrice",
+        "params": [],
+        "id": 1
+    });
+
+    let res: JsonRpcResponse<String> = reqwest::Client::new()
+        .post(alchemy_url())
+        .json(&body)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    let wei = u64::from_str_radix(res.result.trim_start_matches("0x"), 16).unwrap();
+    wei as f64 / 1_000_000_000.0
+}
+
+pub async fn get_provider_url() -> String {
+    alchemy_url()
+}
+
+#[derive(Deserialize)]
+struct FeeHistoryResult {
+    #[serde(rename = "baseFeePerGas")]
+    base_fee_per_gas: Vec<String>,
+    #[serde(rename = "gasUsedRatio")]
+    gas_used_ratio: Vec<f64>,
+    reward: Vec<Vec<String>>,
+}
+
+fn median(mut values: Vec<U256>) -> U256 {
+    values.sort();
+    values[values.len() / 2]
+}
+
+fn next_base_fee(base_fee: U256, gas_used_ratio: f64) -> U256 {
+    if gas_used_ratio > 0.5 {
+        let delta = base_fee * U256::from(((gas_used_ratio - 0.5) * 2.0 * 125.0) as u64) / U256::from(1000);
+        base_fee + delta
+    } else if gas_used_ratio < 0.5 {
+        let delta = base_fee * U256::from(((0.5 - gas_used_ratio) * 2.0 * 125.0) as u64) / U256::from(1000);
+        base_fee - delta
+    } else {
+        base_fee
+    }
+}
+
+/// Returns (slow, normal, fast) fee tiers built from the reward percentile
+/// columns of the last `FEE_HISTORY_BLOCK_COUNT` blocks.
+pub async fn get_fee_estimates() -> (FeeEstimate, FeeEstimate, FeeEstimate) {
+    let body = json!({
+        "jsonrpc": "2.0",
+        "method": "eth_feeHistory",
+        "params": [format!("{:#x}", FEE_HISTORY_BLOCK_COUNT), "latest", REWARD_PERCENTILES],
+        "id": 1
+    });
+
+    let res: JsonRpcResponse<FeeHistoryResult> = reqwest::Client::new()
+        .post(alchemy_url())
+        .json(&body)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    // `baseFeePerGas` has one more entry than `gasUsedRatio`: its last entry
+    // is already the node's own next-block prediction, so the *mined*
+    // block's base fee is one index back from the end, paired with its own
+    // (last) gas-used ratio. Extrapolating from that gives the next block's
+    // base fee without re-applying the adjustment formula on top of a value
+    // that was already extrapolated once.
+    let base_fee_per_gas = &res.result.base_fee_per_gas;
+    let latest_mined_base_fee = U256::from_str_radix(base_fee_per_gas[base_fee_per_gas.len() - 2].trim_start_matches("0x"), 16).unwrap();
+    let latest_ratio = *res.result.gas_used_ratio.last().unwrap();
+    let base_fee = next_base_fee(latest_mined_base_fee, latest_ratio);
+
+    let tiers: Vec<U256> = (0..REWARD_PERCENTILES.len())
+        .map(|i| {
+            let column: Vec<U256> = res.result.reward
+                .iter()
+                .map(|row| U256::from_str_radix(row[i].trim_start_matches("0x"), 16).unwrap())
+                .collect();
+
+            median(column)
+        })
+        .collect();
+
+    let to_estimate = |max_priority_fee: U256| FeeEstimate {
+        base_fee,
+        max_priority_fee,
+        max_fee: base_fee * 2 + max_priority_fee,
+    };
+
+    (to_estimate(tiers[0]), to_estimate(tiers[1]), to_estimate(tiers[2]))
+}
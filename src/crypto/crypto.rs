@@ -0,0 +1,12 @@
+use ethers::types::U256;
+
+pub mod alchemy_api;
+pub mod etherscan_api;
+pub mod trade;
+
+#[derive(Clone, Copy, Debug)]
+pub struct FeeEstimate {
+    pub base_fee: U256,
+    pub max_fee: U256,
+    pub max_priority_fee: U256,
+}
@@ -0,0 +1,47 @@
+use serde::Deserialize;
+use std::env;
+
+fn api_key() -> String {
+    env::var("ETHERSCAN_API_KEY").expect("ETHERSCAN_API_KEY not set")
+}
+
+#[derive(Deserialize)]
+struct EtherscanResponse<T> {
+    result: T,
+}
+
+#[derive(Deserialize)]
+struct EthPriceResult {
+    ethusd: String,
+}
+
+#[derive(Deserialize)]
+struct Transaction {
+    hash: String,
+}
+
+pub async fn get_eth_price() -> f64 {
+    let url = format!("https://api.etherscan.io/api?module=stats&action=ethprice&apikey={}", api_key());
+
+    let res: EtherscanResponse<EthPriceResult> = reqwest::get(url)
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    res.result.ethusd.parse().unwrap()
+}
+
+/// Returns the hash of the most recent transaction (incoming or outgoing) for
+/// `wallet`, or `None` if the wallet has no transactions yet.
+pub async fn get_latest_tx_hash(wallet: &str) -> Option<String> {
+    let url = format!(
+        "https://api.etherscan.io/api?module=account&action=txlist&address={}&sort=desc&page=1&offset=1&apikey={}",
+        wallet, api_key()
+    );
+
+    let res: EtherscanResponse<Vec<Transaction>> = reqwest::get(url).await.ok()?.json().await.ok()?;
+
+    res.result.into_iter().next().map(|tx| tx.hash)
+}
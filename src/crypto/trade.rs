@@ -0,0 +1,186 @@
+use ethers::prelude::*;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use std::env;
+use std::str::FromStr;
+use std::sync::Arc;
+use tokio::sync::OnceCell;
+
+use super::super::{OrderType, TradeToken};
+use super::alchemy_api;
+
+abigen!(
+    UniswapV2Router,
+    r#"[
+        function swapExactETHForTokens(uint amountOutMin, address[] calldata path, address to, uint deadline) external payable returns (uint[] memory amounts)
+        function swapExactTokensForETH(uint amountIn, uint amountOutMin, address[] calldata path, address to, uint deadline) external returns (uint[] memory amounts)
+        function getAmountsOut(uint amountIn, address[] calldata path) external view returns (uint[] memory amounts)
+    ]"#
+);
+
+abigen!(
+    Erc20,
+    r#"[
+        function decimals() external view returns (uint8)
+        function allowance(address owner, address spender) external view returns (uint256)
+        function approve(address spender, uint256 amount) external returns (bool)
+    ]"#
+);
+
+const UNISWAP_V2_ROUTER: &str = "0x7a250d5630B4cF539739dF2C5dAcb4c659F2488D";
+const WETH: &str = "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2";
+const WETH_DECIMALS: u32 = 18;
+const DEADLINE_SECS: u64 = 300;
+
+type SignerProvider = NonceManagerMiddleware<SignerMiddleware<Provider<Http>, LocalWallet>>;
+
+static SIGNER_CLIENT: OnceCell<Arc<SignerProvider>> = OnceCell::const_new();
+static QUOTE_PROVIDER: OnceCell<Arc<Provider<Http>>> = OnceCell::const_new();
+
+async fn build_signer_client() -> Result<Arc<SignerProvider>, Box<dyn std::error::Error + Send + Sync>> {
+    let provider = Provider::<Http>::try_from(alchemy_api::get_provider_url().await)?;
+    let chain_id = provider.get_chainid().await?.as_u64();
+
+    let wallet = env::var("WALLET_PRIVATE_KEY")?.parse::<LocalWallet>()?.with_chain_id(chain_id);
+    let address = wallet.address();
+
+    let signer = SignerMiddleware::new(provider, wallet);
+    let nonce_managed = NonceManagerMiddleware::new(signer, address);
+
+    Ok(Arc::new(nonce_managed))
+}
+
+/// Returns the shared signing client, building (and caching) the
+/// provider/signer/nonce-manager stack on first use instead of
+/// reconstructing it - and re-querying `eth_chainId` - on every call.
+async fn signer_client() -> Result<Arc<SignerProvider>, Box<dyn std::error::Error + Send + Sync>> {
+    SIGNER_CLIENT.get_or_try_init(build_signer_client).await.map(Arc::clone)
+}
+
+/// Returns the shared read-only provider used for quoting, which needs no
+/// `WALLET_PRIVATE_KEY` so a quote preview still works without one configured.
+async fn quote_provider() -> Result<Arc<Provider<Http>>, Box<dyn std::error::Error + Send + Sync>> {
+    QUOTE_PROVIDER
+        .get_or_try_init(|| async { Ok(Arc::new(Provider::<Http>::try_from(alchemy_api::get_provider_url().await)?)) })
+        .await
+        .map(Arc::clone)
+}
+
+/// Converts a decimal token amount into its exact on-chain integer
+/// representation for a token with `decimals` decimal places.
+fn decimal_to_onchain_units(amount: Decimal, decimals: u32) -> Result<U256, Box<dyn std::error::Error + Send + Sync>> {
+    let scale = Decimal::from(10u64.checked_pow(decimals).ok_or("decimals out of range")?);
+    let scaled = amount.checked_mul(scale).ok_or("decimal overflow while scaling to on-chain units")?;
+    let units = scaled.trunc().to_u128().ok_or("decimal overflow while converting to on-chain units")?;
+
+    Ok(U256::from(units))
+}
+
+/// Converts an on-chain integer amount back into a decimal token amount for
+/// a token with `decimals` decimal places.
+fn onchain_units_to_decimal(units: U256, decimals: u32) -> Result<Decimal, Box<dyn std::error::Error + Send + Sync>> {
+    let scale = Decimal::from(10u64.checked_pow(decimals).ok_or("decimals out of range")?);
+    let value = Decimal::from(units.as_u128());
+
+    value.checked_div(scale).ok_or_else(|| "decimal overflow while converting from on-chain units".into())
+}
+
+async fn quote_path(tt: &TradeToken) -> Result<(Decimal, u32), Box<dyn std::error::Error + Send + Sync>> {
+    let client = quote_provider().await?;
+    let contract = Address::from_str(tt.contract.as_ref().unwrap())?;
+    let weth = Address::from_str(WETH)?;
+    let router = UniswapV2Router::new(Address::from_str(UNISWAP_V2_ROUTER)?, client.clone());
+    let token = Erc20::new(contract, client.clone());
+    let token_decimals = token.decimals().call().await? as u32;
+    let amount = tt.amount.unwrap();
+
+    let (path, amount_in, out_decimals) = match tt.order_type {
+        OrderType::Buy => (vec![weth, contract], decimal_to_onchain_units(amount, WETH_DECIMALS)?, token_decimals),
+        OrderType::Sell => (vec![contract, weth], decimal_to_onchain_units(amount, token_decimals)?, WETH_DECIMALS),
+    };
+
+    let amounts = router.get_amounts_out(amount_in, path).call().await?;
+    let quoted_units = *amounts.last().ok_or("empty getAmountsOut response")?;
+
+    Ok((onchain_units_to_decimal(quoted_units, out_decimals)?, out_decimals))
+}
+
+/// Fetches the current expected output amount for `tt` through
+/// `getAmountsOut`, in human-readable decimal units of the output asset.
+pub async fn get_quote(tt: &TradeToken) -> Result<Decimal, Box<dyn std::error::Error + Send + Sync>> {
+    let (quoted_out, _) = quote_path(tt).await?;
+    Ok(quoted_out)
+}
+
+/// Fetches the current effective price (output units per 1 input unit) for `tt`.
+pub async fn get_price(tt: &TradeToken) -> Result<Decimal, Box<dyn std::error::Error + Send + Sync>> {
+    let quoted_out = get_quote(tt).await?;
+    quoted_out.checked_div(tt.amount.unwrap()).ok_or_else(|| "decimal overflow while computing price".into())
+}
+
+/// Ensures the router holds at least `amount_in` allowance over `token` for
+/// the connected wallet, submitting (and awaiting) an `approve` tx first if
+/// it doesn't. `swapExactTokensForETH` pulls the input token via
+/// `transferFrom`, so without this a Sell always reverts on-chain.
+async fn ensure_allowance(token: &Erc20<SignerProvider>, owner: Address, spender: Address, amount_in: U256) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let current_allowance = token.allowance(owner, spender).call().await?;
+    if current_allowance >= amount_in {
+        return Ok(());
+    }
+
+    token.approve(spender, amount_in).send().await?.await?;
+
+    Ok(())
+}
+
+/// Builds, signs and broadcasts a Uniswap V2 swap for `tt` through the
+/// signer -> nonce manager middleware stack and returns the broadcast tx hash.
+pub async fn execute_swap(tt: &TradeToken) -> Result<TxHash, Box<dyn std::error::Error + Send + Sync>> {
+    let client = signer_client().await?;
+
+    let contract = Address::from_str(tt.contract.as_ref().unwrap())?;
+    let weth = Address::from_str(WETH)?;
+    let router = UniswapV2Router::new(Address::from_str(UNISWAP_V2_ROUTER)?, client.clone());
+    let token = Erc20::new(contract, client.clone());
+    let token_decimals = token.decimals().call().await? as u32;
+
+    let to = client.address();
+    let deadline = U256::from(std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH)?.as_secs() + DEADLINE_SECS);
+    let amount = tt.amount.unwrap();
+    let quoted_out = get_quote(tt).await?;
+    let min_amount_out = tt.min_amount_out(quoted_out)?;
+    let (_, normal_fee, _) = alchemy_api::get_fee_estimates().await;
+
+    let pending_tx = match tt.order_type {
+        OrderType::Buy => {
+            let amount_in = decimal_to_onchain_units(amount, WETH_DECIMALS)?;
+            let min_out = decimal_to_onchain_units(min_amount_out, token_decimals)?;
+            let path = vec![weth, contract];
+
+            router
+                .swap_exact_eth_for_tokens(min_out, path, to, deadline)
+                .value(amount_in)
+                .max_fee_per_gas(normal_fee.max_fee)
+                .max_priority_fee_per_gas(normal_fee.max_priority_fee)
+                .send()
+                .await?
+        }
+        OrderType::Sell => {
+            let amount_in = decimal_to_onchain_units(amount, token_decimals)?;
+            let min_out = decimal_to_onchain_units(min_amount_out, WETH_DECIMALS)?;
+            let path = vec![contract, weth];
+            let router_address = Address::from_str(UNISWAP_V2_ROUTER)?;
+
+            ensure_allowance(&token, to, router_address, amount_in).await?;
+
+            router
+                .swap_exact_tokens_for_eth(amount_in, min_out, path, to, deadline)
+                .max_fee_per_gas(normal_fee.max_fee)
+                .max_priority_fee_per_gas(normal_fee.max_priority_fee)
+                .send()
+                .await?
+        }
+    };
+
+    Ok(pending_tx.tx_hash())
+}
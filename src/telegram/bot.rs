@@ -5,14 +5,17 @@ use teloxide::{
         types::{InlineKeyboardButton, InlineKeyboardMarkup},
         utils::command::{parse_command, BotCommands}
 };
-use lazy_static::lazy_static;
+use rust_decimal::Decimal;
 use core::fmt;
-use std::{str::FromStr, sync::Mutex};
+use std::str::FromStr;
 
 #[path ="../crypto/crypto.rs"]
 mod crypto;
+mod snipe;
+mod watcher;
 use crypto::alchemy_api;
 use crypto::etherscan_api;
+use crypto::trade;
 
 type MyDialogue = Dialogue<State, InMemStorage<State>>;
 type HandlerResult = Result<(), Box<dyn std::error::Error + Send + Sync>>;
@@ -46,8 +49,8 @@ impl FromStr for OrderType {
 #[derive(Clone, Debug)]
 struct TradeToken {
     contract: Option<String>,
-    amount: Option<f64>,
-    slippage: Option<f32>,
+    amount: Option<Decimal>,
+    slippage: Option<Decimal>,
     order_type: OrderType,
 }
 
@@ -61,11 +64,40 @@ impl fmt::Display for TradeToken {
     }
 }
 
+#[derive(Debug)]
+enum TradeTokenError {
+    Overflow,
+}
+
+impl fmt::Display for TradeTokenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TradeTokenError::Overflow => write!(f, "decimal arithmetic overflowed while computing trade amounts")
+        }
+    }
+}
+
+impl std::error::Error for TradeTokenError {}
+
+impl TradeToken {
+    /// Computes `quoted_out * (1 - slippage / 100)` using checked decimal
+    /// arithmetic, returning an error instead of NaN on overflow.
+    fn min_amount_out(&self, quoted_out: Decimal) -> Result<Decimal, TradeTokenError> {
+        let hundred = Decimal::from(100);
+        let slippage = self.slippage.unwrap();
+
+        let factor = hundred.checked_sub(slippage).ok_or(TradeTokenError::Overflow)?
+            .checked_div(hundred).ok_or(TradeTokenError::Overflow)?;
+
+        quoted_out.checked_mul(factor).ok_or(TradeTokenError::Overflow)
+    }
+}
+
 #[derive(Clone, Default)]
 pub enum State {
     #[default]
     Start,
-    Confirm,
+    Confirm(TradeToken),
 }
 
 #[derive(BotCommands, Clone, Debug)]
@@ -85,22 +117,24 @@ enum Command {
     Gas,
     #[command(description = "start monitoring etherum wallets")]
     Watch(String),
+    #[command(description = "stop monitoring watched wallets")]
+    Unwatch,
+    #[command(description = "queue an automatic snipe order: <contract> <amount> <slippage> <target_price>, where target_price is the max cost per token in ETH")]
+    Snipe(String),
+    #[command(description = "cancel a queued snipe order: <id>")]
+    Cancelsnipe(String),
     #[command(description = "cancel current command")]
     Cancel,
 }
 
-lazy_static! {
-    static ref TRADE_TOKEN: Mutex<TradeToken> = Mutex::new(TradeToken { contract: None, amount: None, slippage: None, order_type: OrderType::Buy });
-    static ref WATCHED_WALLETS: Mutex<Vec<String>> = Mutex::new(Vec::new());
-}
-
-
 #[tokio::main]
 pub async fn main() {
     pretty_env_logger::init();
     log::info!("Starting command bot...");
 
     let bot = Bot::from_env();
+    watcher::init(bot.clone());
+    snipe::init(bot.clone());
 
     Dispatcher::builder(bot, schema())
         .dependencies(dptree::deps![InMemStorage::<State>::new()])
@@ -115,15 +149,15 @@ fn schema() -> UpdateHandler<Box<dyn std::error::Error + Send + Sync + 'static>>
     use dptree::case;
 
     let command_handler = teloxide::filter_command::<Command, _>()
-        .branch(
-            case![State::Start]
-            .branch(case![Command::Buy(tt)].endpoint(trade_token))
-            .branch(case![Command::Sell(tt)].endpoint(trade_token))
-            .branch(case![Command::Balance].endpoint(get_eth_balance))
-            .branch(case![Command::Tokens].endpoint(get_erc20_balances))
-            .branch(case![Command::Gas].endpoint(get_eth_gas))
-        )
+        .branch(case![Command::Buy(tt)].endpoint(trade_token))
+        .branch(case![Command::Sell(tt)].endpoint(trade_token))
+        .branch(case![Command::Balance].endpoint(get_eth_balance))
+        .branch(case![Command::Tokens].endpoint(get_erc20_balances))
+        .branch(case![Command::Gas].endpoint(get_eth_gas))
         .branch(case![Command::Watch(w)].endpoint(watch_wallets))
+        .branch(case![Command::Unwatch].endpoint(unwatch_wallets))
+        .branch(case![Command::Snipe(s)].endpoint(snipe_token))
+        .branch(case![Command::Cancelsnipe(id)].endpoint(cancel_snipe))
         .branch(case![Command::Help].endpoint(help))
         .branch(case![Command::Cancel].endpoint(cancel));
 
@@ -132,7 +166,7 @@ fn schema() -> UpdateHandler<Box<dyn std::error::Error + Send + Sync + 'static>>
         .branch(dptree::endpoint(invalid_state));
 
     let callback_query_handler = Update::filter_callback_query()
-        .branch(case![State::Confirm].endpoint(confirm));
+        .branch(case![State::Confirm(tt)].endpoint(confirm));
 
     dialogue::enter::<Update, InMemStorage<State>, State, _>()
         .branch(message_handler)
@@ -163,22 +197,36 @@ fn validate_tradetoken_args(args: &Vec<&str>, order_type: OrderType) -> Option<T
         trade_token.contract = None;
     }
 
-    trade_token.amount = match args[1].parse() {
-        Ok(v) => Some(v),
-        Err(_) => None
+    trade_token.amount = match args[1].parse::<Decimal>() {
+        Ok(v) if v > Decimal::ZERO => Some(v),
+        _ => None
     };
 
-    trade_token.slippage = match args[2].parse() {
-        Ok(v) => Some(v),
-        Err(_) => None
+    trade_token.slippage = match args[2].parse::<Decimal>() {
+        Ok(v) if v >= Decimal::ZERO && v <= Decimal::from(100) => Some(v),
+        _ => None
     };
 
-    let mut tt = TRADE_TOKEN.lock().unwrap();
-    *tt = trade_token.clone();
-
     Some(trade_token)
 }
 
+fn validate_snipe_args(args: &Vec<&str>) -> Option<(TradeToken, Decimal)> {
+    if args.len() != 4 {
+        return None;
+    }
+
+    let trade_token = validate_tradetoken_args(&args[..3].to_vec(), OrderType::Buy)?;
+
+    if trade_token.contract.is_none() || trade_token.amount.is_none() || trade_token.slippage.is_none() {
+        return None;
+    }
+
+    match args[3].parse::<Decimal>() {
+        Ok(target_price) if target_price > Decimal::ZERO => Some((trade_token, target_price)),
+        _ => None
+    }
+}
+
 fn validate_watchwallets_args(args: &Vec<&str>) -> Option<Vec<String>> {
     let mut watched_wallets: Vec<String> = vec![];
 
@@ -189,9 +237,6 @@ fn validate_watchwallets_args(args: &Vec<&str>) -> Option<Vec<String>> {
         }
     }
 
-    let mut ww = WATCHED_WALLETS.lock().unwrap();
-    *ww = watched_wallets.clone();
-
     if watched_wallets.is_empty() { None }  else { Some(watched_wallets) }
 }
 
@@ -234,10 +279,20 @@ async fn trade_token(bot: Bot, dialogue: MyDialogue, msg: Message) -> HandlerRes
     };
 
     if !incorrect_params {
-        bot.send_message(msg.chat.id, format!("{}", trade_token.clone().unwrap())).await?;
+        let tt = trade_token.clone().unwrap();
+
+        let quote = match trade::get_quote(&tt).await {
+            Ok(quoted_out) => {
+                let price = quoted_out.checked_div(tt.amount.unwrap());
+                format!("\n📈 Expected output: {}\n💱 Price: {} per unit", quoted_out, price.map(|p| p.to_string()).unwrap_or_else(|| "n/a".to_string()))
+            }
+            Err(_) => String::new()
+        };
+
+        bot.send_message(msg.chat.id, format!("{}{}", tt, quote)).await?;
         bot.send_message(msg.chat.id, "Do you want to execute the transaction?").reply_markup(make_yes_no_keyboard()).await?;
 
-        dialogue.update(State::Confirm).await?;
+        dialogue.update(State::Confirm(tt)).await?;
     } else {
         dialogue.exit().await?;
     }
@@ -245,7 +300,41 @@ async fn trade_token(bot: Bot, dialogue: MyDialogue, msg: Message) -> HandlerRes
     Ok(())
 }
 
-async fn confirm(bot: Bot, dialogue: MyDialogue, q: CallbackQuery) -> HandlerResult {
+async fn snipe_token(bot: Bot, msg: Message) -> HandlerResult {
+    let (_, args) = parse_command(msg.text().unwrap(), bot.get_me().await.unwrap().username()).unwrap();
+
+    match validate_snipe_args(&args) {
+        Some((trade_token, target_price)) => {
+            let orders = snipe::queue(msg.chat.id, trade_token, target_price);
+            let order_id = orders.last().unwrap().id;
+
+            bot.send_message(msg.chat.id, format!("🎯 Snipe #{} queued! Target price: {}\nYou have {} snipe(s) queued.", order_id, target_price, orders.len())).await?;
+        }
+        None => {
+            bot.send_message(msg.chat.id, "Snipe cancelled: submitted parameters are incorrect!").await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn cancel_snipe(bot: Bot, msg: Message) -> HandlerResult {
+    let (_, args) = parse_command(msg.text().unwrap(), bot.get_me().await.unwrap().username()).unwrap();
+    let id: Option<u32> = args.get(0).and_then(|a| a.parse().ok());
+
+    match id.map(|id| (id, snipe::cancel(msg.chat.id, id))) {
+        Some((id, true)) => {
+            bot.send_message(msg.chat.id, format!("Snipe #{} cancelled", id)).await?;
+        }
+        _ => {
+            bot.send_message(msg.chat.id, "Cancel snipe failed: unknown snipe id").await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn confirm(bot: Bot, dialogue: MyDialogue, tt: TradeToken, q: CallbackQuery) -> HandlerResult {
     let chat_id = q.chat_id().unwrap();
 
     match q.clone().data {
@@ -255,8 +344,14 @@ async fn confirm(bot: Bot, dialogue: MyDialogue, q: CallbackQuery) -> HandlerRes
             bot.delete_message(chat_id, q.message.unwrap().id).await?;
 
             if d == "yes" {
-                bot.send_message(chat_id, format!("Transaction executed!")).await?;
-                // TODO: handle transaction
+                match trade::execute_swap(&tt).await {
+                    Ok(tx_hash) => {
+                        bot.send_message(chat_id, format!("Transaction executed!\n🔗 Tx hash: {:#x}", tx_hash)).await?;
+                    }
+                    Err(e) => {
+                        bot.send_message(chat_id, format!("Transaction failed: {}", e)).await?;
+                    }
+                }
             } else if d == "no" {
                 bot.send_message(chat_id, format!("Transaction was not executed!")).await?;
             }
@@ -281,7 +376,7 @@ async fn watch_wallets(bot: Bot, msg: Message) -> HandlerResult {
 
     match wallets {
         Some(v) => {
-            // TODO: handle watching wallets
+            watcher::subscribe(msg.chat.id, v.clone());
 
             let mut message: String = String::from("Wallets to watch:\n");
             let mut counter: u8 = 0;
@@ -301,6 +396,16 @@ async fn watch_wallets(bot: Bot, msg: Message) -> HandlerResult {
     Ok(())
 }
 
+async fn unwatch_wallets(bot: Bot, msg: Message) -> HandlerResult {
+    if watcher::unsubscribe(msg.chat.id) {
+        bot.send_message(msg.chat.id, "Stopped watching your wallets").await?;
+    } else {
+        bot.send_message(msg.chat.id, "You are not watching any wallets").await?;
+    }
+
+    Ok(())
+}
+
 async fn get_erc20_balances(bot: Bot, msg: Message) -> HandlerResult {
     let token_balances = alchemy_api::get_token_balances().await;
     let mut message: String = String::from("ERC-20 Token balances:\n");
@@ -315,14 +420,19 @@ async fn get_erc20_balances(bot: Bot, msg: Message) -> HandlerResult {
 }
 
 async fn get_eth_gas(bot: Bot, msg: Message) -> HandlerResult {
-    // gas estimations based on cryptoneur.xyz/en/gas-fees-calculator
-    let gwei_fee = alchemy_api::get_gas().await;
+    let (slow, normal, fast) = alchemy_api::get_fee_estimates().await;
     let eth_price: f64 = etherscan_api::get_eth_price().await;
 
-    let uniswap_v2: f64 = gwei_fee * 0.000000001 * eth_price * 152809.0 * 1.03;
-    let uniswap_v3: f64 = gwei_fee * 0.000000001 * eth_price * 184523.0 * 1.03;
+    let to_gwei = |wei: ethers::types::U256| wei.as_u64() as f64 / 1_000_000_000.0;
+    let to_usd = |fee_per_gas: ethers::types::U256| fee_per_gas.as_u64() as f64 * 152809.0 / 1_000_000_000_000_000_000.0 * eth_price;
 
-    let response = format!("Current eth gas is: {:.0} gwei\n\nEstimated fees:\n🦄 Uniswap V2 swap: {:.2} $\n🦄 Uniswap V3 swap: {:.2} $", gwei_fee, uniswap_v2, uniswap_v3);
+    let response = format!(
+        "Current base fee is: {:.0} gwei\n\nEstimated maxFeePerGas / Uniswap V2 swap cost:\n🐢 Slow: {:.0} gwei ({:.2} $)\n🚗 Normal: {:.0} gwei ({:.2} $)\n🚀 Fast: {:.0} gwei ({:.2} $)",
+        to_gwei(normal.base_fee),
+        to_gwei(slow.max_fee), to_usd(slow.max_fee),
+        to_gwei(normal.max_fee), to_usd(normal.max_fee),
+        to_gwei(fast.max_fee), to_usd(fast.max_fee),
+    );
     bot.send_message(msg.chat.id, response).await?;
     Ok(())
 }
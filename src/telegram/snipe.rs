@@ -0,0 +1,112 @@
+use lazy_static::lazy_static;
+use rust_decimal::Decimal;
+use teloxide::prelude::*;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use super::crypto::trade;
+use super::TradeToken;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+#[derive(Clone, Debug)]
+pub struct SnipeOrder {
+    pub id: u32,
+    pub trade_token: TradeToken,
+    /// Cost to acquire one output token, in input-asset units (e.g. ETH per
+    /// token for a buy) — the reciprocal of `trade::get_price`'s
+    /// output-per-input convention. A snipe fires once the effective price
+    /// falls to or below this value, matching a classic buy-limit order.
+    pub target_price: Decimal,
+}
+
+lazy_static! {
+    static ref SNIPES: Mutex<HashMap<ChatId, Vec<SnipeOrder>>> = Mutex::new(HashMap::new());
+}
+
+static NEXT_ID: AtomicU32 = AtomicU32::new(1);
+
+/// Queues a new snipe order for `chat_id` and returns the currently queued
+/// orders for that chat (including the new one).
+pub fn queue(chat_id: ChatId, trade_token: TradeToken, target_price: Decimal) -> Vec<SnipeOrder> {
+    let order = SnipeOrder { id: NEXT_ID.fetch_add(1, Ordering::Relaxed), trade_token, target_price };
+
+    let mut snipes = SNIPES.lock().unwrap();
+    let orders = snipes.entry(chat_id).or_insert_with(Vec::new);
+    orders.push(order);
+
+    orders.clone()
+}
+
+/// Returns the currently queued snipe orders for `chat_id`.
+pub fn list(chat_id: ChatId) -> Vec<SnipeOrder> {
+    SNIPES.lock().unwrap().get(&chat_id).cloned().unwrap_or_default()
+}
+
+/// Cancels the queued snipe order `id` for `chat_id`. Returns `true` if an
+/// order was removed.
+pub fn cancel(chat_id: ChatId, id: u32) -> bool {
+    let mut snipes = SNIPES.lock().unwrap();
+
+    let Some(orders) = snipes.get_mut(&chat_id) else {
+        return false;
+    };
+
+    let len_before = orders.len();
+    orders.retain(|o| o.id != id);
+    let removed = orders.len() != len_before;
+
+    if orders.is_empty() {
+        snipes.remove(&chat_id);
+    }
+
+    removed
+}
+
+/// Polls every queued snipe order on a fixed interval and executes the swap
+/// once its effective price crosses the order's target price.
+async fn poll_task(bot: Bot) {
+    let mut ticker = tokio::time::interval(POLL_INTERVAL);
+
+    loop {
+        ticker.tick().await;
+
+        let due: Vec<(ChatId, SnipeOrder)> = SNIPES.lock().unwrap()
+            .iter()
+            .flat_map(|(chat_id, orders)| orders.iter().map(move |o| (*chat_id, o.clone())))
+            .collect();
+
+        for (chat_id, order) in due {
+            let Ok(tokens_per_input) = trade::get_price(&order.trade_token).await else {
+                continue;
+            };
+
+            // `trade::get_price` is output-per-input (tokens per ETH for a
+            // buy); invert it to the cost-per-token terms `target_price` is
+            // expressed in before comparing.
+            let Some(cost_per_unit) = Decimal::ONE.checked_div(tokens_per_input) else {
+                continue;
+            };
+
+            if cost_per_unit <= order.target_price {
+                cancel(chat_id, order.id);
+
+                match trade::execute_swap(&order.trade_token).await {
+                    Ok(tx_hash) => {
+                        let _ = bot.send_message(chat_id, format!("🎯 Snipe #{} triggered!\n🔗 Tx hash: {:#x}", order.id, tx_hash)).await;
+                    }
+                    Err(e) => {
+                        let _ = bot.send_message(chat_id, format!("Snipe #{} failed: {}", order.id, e)).await;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Spawns the background snipe poller. Call once on startup.
+pub fn init(bot: Bot) {
+    tokio::spawn(poll_task(bot));
+}
@@ -0,0 +1,103 @@
+use lazy_static::lazy_static;
+use teloxide::prelude::*;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::broadcast;
+
+use super::crypto::etherscan_api;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(15);
+const EVENT_CHANNEL_CAPACITY: usize = 100;
+
+#[derive(Clone, Debug)]
+struct WalletEvent {
+    wallet: String,
+    tx_hash: String,
+}
+
+lazy_static! {
+    static ref EVENT_TX: broadcast::Sender<WalletEvent> = broadcast::channel(EVENT_CHANNEL_CAPACITY).0;
+    static ref SUBSCRIPTIONS: Mutex<HashMap<ChatId, Vec<String>>> = Mutex::new(HashMap::new());
+    static ref LAST_SEEN: Mutex<HashMap<String, String>> = Mutex::new(HashMap::new());
+}
+
+pub fn subscribe(chat_id: ChatId, wallets: Vec<String>) {
+    SUBSCRIPTIONS.lock().unwrap().insert(chat_id, wallets);
+}
+
+pub fn unsubscribe(chat_id: ChatId) -> bool {
+    SUBSCRIPTIONS.lock().unwrap().remove(&chat_id).is_some()
+}
+
+fn watched_wallets() -> Vec<String> {
+    let mut wallets: Vec<String> = SUBSCRIPTIONS.lock().unwrap().values().flatten().cloned().collect();
+    wallets.sort();
+    wallets.dedup();
+    wallets
+}
+
+fn subscribers_for(wallet: &str) -> Vec<ChatId> {
+    SUBSCRIPTIONS.lock().unwrap()
+        .iter()
+        .filter(|(_, wallets)| wallets.iter().any(|w| w == wallet))
+        .map(|(chat_id, _)| *chat_id)
+        .collect()
+}
+
+/// Polls every watched wallet on a fixed interval and publishes a
+/// `WalletEvent` whenever its latest transaction hash changes.
+async fn detection_task() {
+    let mut ticker = tokio::time::interval(POLL_INTERVAL);
+
+    loop {
+        ticker.tick().await;
+
+        for wallet in watched_wallets() {
+            let Some(tx_hash) = etherscan_api::get_latest_tx_hash(&wallet).await else {
+                continue;
+            };
+
+            let mut last_seen = LAST_SEEN.lock().unwrap();
+            let previous = last_seen.insert(wallet.clone(), tx_hash.clone());
+            drop(last_seen);
+
+            // A missing previous entry means this is the first poll since the
+            // wallet was subscribed, so its latest tx is the baseline, not a
+            // new event.
+            let is_new = matches!(previous, Some(prev) if prev != tx_hash);
+
+            if is_new {
+                let _ = EVENT_TX.send(WalletEvent { wallet, tx_hash });
+            }
+        }
+    }
+}
+
+/// Fans out published `WalletEvent`s to every chat currently watching the
+/// wallet that triggered them.
+async fn alert_task(bot: Bot) {
+    let mut events = EVENT_TX.subscribe();
+
+    loop {
+        let event = match events.recv().await {
+            Ok(event) => event,
+            Err(broadcast::error::RecvError::Lagged(n)) => {
+                log::warn!("alert_task lagged behind the wallet-event stream by {n} events; some alerts were dropped");
+                continue;
+            }
+            Err(broadcast::error::RecvError::Closed) => break,
+        };
+
+        for chat_id in subscribers_for(&event.wallet) {
+            let message = format!("🚨 Watched wallet activity!\n📄 Wallet: {}\n🔗 Tx hash: {}", event.wallet, event.tx_hash);
+            let _ = bot.send_message(chat_id, message).await;
+        }
+    }
+}
+
+/// Spawns the background poller and alert fan-out task. Call once on startup.
+pub fn init(bot: Bot) {
+    tokio::spawn(detection_task());
+    tokio::spawn(alert_task(bot));
+}